@@ -0,0 +1,195 @@
+// contracts/admin-auth/src/lib.rs
+#![no_std]
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+// M-of-N signer set guarding admin operations
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AdminSigners {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+// Instance storage keys owned by this crate
+#[contracttype]
+pub enum AdminKey {
+    Admin,
+    PendingAdmin,
+    AdminSigners,
+}
+
+/// Store the admin (used by contract initializers)
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&AdminKey::Admin, admin);
+}
+
+/// Get current admin
+pub fn get_admin(env: &Env) -> Address {
+    env.storage().instance().get(&AdminKey::Admin).unwrap()
+}
+
+/// Get pending admin, if a handoff is in progress
+pub fn get_pending_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&AdminKey::PendingAdmin)
+}
+
+/// Get admin signer set, if multisig is enabled
+pub fn get_admin_signers(env: &Env) -> Option<AdminSigners> {
+    env.storage().instance().get(&AdminKey::AdminSigners)
+}
+
+/// Propose a new admin (takes effect once the new admin accepts)
+pub fn propose_admin(env: &Env, new_admin: &Address, approvers: &Vec<Address>) {
+    require_admin(env, approvers);
+    
+    env.storage().instance().set(&AdminKey::PendingAdmin, new_admin);
+}
+
+/// Accept a pending admin handoff
+pub fn accept_admin(env: &Env) {
+    let pending = get_pending_admin(env).expect("No pending admin");
+    pending.require_auth();
+    
+    set_admin(env, &pending);
+    env.storage().instance().remove(&AdminKey::PendingAdmin);
+}
+
+/// Set the M-of-N signer set that replaces admin auth for admin operations (threshold 0 disables it)
+pub fn set_admin_signers(env: &Env, signers: Vec<Address>, threshold: u32, approvers: &Vec<Address>) {
+    require_admin(env, approvers);
+    
+    if threshold == 0 {
+        env.storage().instance().remove(&AdminKey::AdminSigners);
+        return;
+    }
+    
+    assert!(threshold <= signers.len(), "Threshold exceeds signer count");
+    for i in 0..signers.len() {
+        let signer = signers.get(i).unwrap();
+        assert!(signers.first_index_of(&signer) == Some(i), "Duplicate signer");
+    }
+    
+    env.storage().instance().set(&AdminKey::AdminSigners, &AdminSigners { signers, threshold });
+}
+
+/// Require admin auth, or M-of-N approvers when a signer set is configured,
+/// so a signer quorum can still act (e.g. rotate the admin) if the admin key is lost
+pub fn require_admin(env: &Env, approvers: &Vec<Address>) -> Address {
+    let admin = get_admin(env);
+    
+    match get_admin_signers(env) {
+        Some(set) => {
+            let mut approved: Vec<Address> = Vec::new(env);
+            for approver in approvers.iter() {
+                assert!(set.signers.contains(&approver), "Approver not in signer set");
+                assert!(!approved.contains(&approver), "Duplicate approver");
+                approver.require_auth();
+                approved.push_back(approver);
+            }
+            assert!(approved.len() >= set.threshold, "Not enough approvers");
+        }
+        None => admin.require_auth(),
+    }
+    
+    admin
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+    
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _};
+    
+    // Minimal contract exposing the admin entrypoints the way the trybud contracts do
+    #[contract]
+    struct AdminContract;
+    
+    #[contractimpl]
+    impl AdminContract {
+        pub fn initialize(env: Env, admin: Address) {
+            set_admin(&env, &admin);
+        }
+        
+        pub fn propose_admin(env: Env, new_admin: Address, approvers: Vec<Address>) {
+            super::propose_admin(&env, &new_admin, &approvers);
+        }
+        
+        pub fn accept_admin(env: Env) {
+            super::accept_admin(&env);
+        }
+        
+        pub fn set_admin_signers(env: Env, signers: Vec<Address>, threshold: u32, approvers: Vec<Address>) {
+            super::set_admin_signers(&env, signers, threshold, &approvers);
+        }
+        
+        pub fn get_admin(env: Env) -> Address {
+            super::get_admin(&env)
+        }
+        
+        pub fn get_pending_admin(env: Env) -> Option<Address> {
+            super::get_pending_admin(&env)
+        }
+        
+        pub fn get_admin_signers(env: Env) -> Option<AdminSigners> {
+            super::get_admin_signers(&env)
+        }
+    }
+    
+    fn setup(env: &Env) -> AdminContractClient<'_> {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AdminContract);
+        let client = AdminContractClient::new(env, &contract_id);
+        client.initialize(&Address::generate(env));
+        client
+    }
+    
+    #[test]
+    fn admin_handoff_needs_new_admin_to_accept() {
+        let env = Env::default();
+        let client = setup(&env);
+        let new_admin = Address::generate(&env);
+        
+        assert!(client.try_accept_admin().is_err());
+        client.propose_admin(&new_admin, &Vec::new(&env));
+        assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+        
+        client.accept_admin();
+        assert_eq!(env.auths()[0].0, new_admin);
+        assert_eq!(client.get_admin(), new_admin);
+        assert_eq!(client.get_pending_admin(), None);
+    }
+    
+    #[test]
+    fn signer_quorum_replaces_admin_auth() {
+        let env = Env::default();
+        let client = setup(&env);
+        let admin = client.get_admin();
+        let (a, b) = (Address::generate(&env), Address::generate(&env));
+        let signers = Vec::from_array(&env, [a.clone(), b.clone(), Address::generate(&env)]);
+        
+        assert!(client.try_set_admin_signers(&signers, &4, &Vec::new(&env)).is_err());
+        assert!(client.try_set_admin_signers(&Vec::from_array(&env, [a.clone(), a.clone()]), &1, &Vec::new(&env)).is_err());
+        client.set_admin_signers(&signers, &2, &Vec::new(&env));
+        assert_eq!(client.get_admin_signers().unwrap().threshold, 2);
+        
+        // Short, duplicate and outside approvals are rejected
+        let new_admin = Address::generate(&env);
+        assert!(client.try_propose_admin(&new_admin, &Vec::from_array(&env, [a.clone()])).is_err());
+        assert!(client.try_propose_admin(&new_admin, &Vec::from_array(&env, [a.clone(), a.clone()])).is_err());
+        assert!(client.try_propose_admin(&new_admin, &Vec::from_array(&env, [a.clone(), admin.clone()])).is_err());
+        
+        // A quorum rotates the admin without the admin's signature
+        client.propose_admin(&new_admin, &Vec::from_array(&env, [a.clone(), b.clone()]));
+        let signed: std::vec::Vec<Address> = env.auths().into_iter().map(|(address, _)| address).collect();
+        assert!(signed.contains(&a) && signed.contains(&b) && !signed.contains(&admin));
+        client.accept_admin();
+        assert_eq!(client.get_admin(), new_admin);
+        
+        // Threshold 0 hands control back to the admin alone
+        client.set_admin_signers(&Vec::new(&env), &0, &Vec::from_array(&env, [a, b]));
+        assert!(client.get_admin_signers().is_none());
+        client.propose_admin(&admin, &Vec::new(&env));
+        assert_eq!(client.get_pending_admin(), Some(admin));
+    }
+}
@@ -1,5 +1,6 @@
 // contracts/badge/src/lib.rs
 #![no_std]
+use admin_auth::AdminSigners;
 use soroban_sdk::{
     contract, contractimpl, contracttype, Address, Env, String, Vec
 };
@@ -26,6 +27,7 @@ pub enum AchievementType {
     Streak30,                // 30-day streak
     Applications100,         // 100 total applications
     MultiQuest5,            // 5 quests completed
+    OfferLanded,             // Closed a quest early with a verified job offer
 }
 
 // Badge metadata
@@ -52,7 +54,6 @@ pub struct BadgeCollection {
 
 #[contracttype]
 pub enum DataKey {
-    Admin,
     QuestContract,
     BadgeCounter,
     Badge(u64),
@@ -71,11 +72,46 @@ impl BadgeContract {
     pub fn initialize(env: Env, admin: Address, quest_contract: Address) {
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Admin, &admin);
+        admin_auth::set_admin(&env, &admin);
         env.storage().instance().set(&DataKey::QuestContract, &quest_contract);
         env.storage().instance().set(&DataKey::BadgeCounter, &0u64);
     }
     
+    /// Propose a new admin (takes effect once the new admin accepts)
+    pub fn propose_admin(env: Env, new_admin: Address, approvers: Vec<Address>) {
+        admin_auth::propose_admin(&env, &new_admin, &approvers);
+    }
+    
+    /// Accept a pending admin handoff
+    pub fn accept_admin(env: Env) {
+        admin_auth::accept_admin(&env);
+    }
+    
+    /// Set the M-of-N signer set that replaces admin auth for admin operations (threshold 0 disables it)
+    pub fn set_admin_signers(
+        env: Env,
+        signers: Vec<Address>,
+        threshold: u32,
+        approvers: Vec<Address>,
+    ) {
+        admin_auth::set_admin_signers(&env, signers, threshold, &approvers);
+    }
+    
+    /// Get current admin
+    pub fn get_admin(env: Env) -> Address {
+        admin_auth::get_admin(&env)
+    }
+    
+    /// Get pending admin, if a handoff is in progress
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        admin_auth::get_pending_admin(&env)
+    }
+    
+    /// Get admin signer set, if multisig is enabled
+    pub fn get_admin_signers(env: Env) -> Option<AdminSigners> {
+        admin_auth::get_admin_signers(&env)
+    }
+    
     /// Mint quest completion badge
     pub fn mint_quest_badge(
         env: Env,
@@ -84,11 +120,11 @@ impl BadgeContract {
         duration_days: u32,
         is_perfect: bool,
         is_overachiever: bool,
+        approvers: Vec<Address>,
     ) -> Vec<u64> {
         // For testing: allow admin to mint badges
         // In production, only quest contract should call this
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+        admin_auth::require_admin(&env, &approvers);
         
         let mut minted_badges = Vec::new(&env);
         
@@ -146,11 +182,15 @@ impl BadgeContract {
     }
     
     /// Mint streak achievement badge
-    pub fn mint_streak_badge(env: Env, owner: Address, streak_days: u32) -> u64 {
+    pub fn mint_streak_badge(
+        env: Env,
+        owner: Address,
+        streak_days: u32,
+        approvers: Vec<Address>,
+    ) -> u64 {
         // For testing: allow admin to mint badges
         // In production, only quest contract should call this
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+        admin_auth::require_admin(&env, &approvers);
         
         let achievement = if streak_days >= 30 {
             AchievementType::Streak30
@@ -173,6 +213,21 @@ impl BadgeContract {
         )
     }
     
+    /// Mint offer-landed badge (quest contract only)
+    pub fn mint_offer_badge(env: Env, owner: Address, quest_id: u64) -> u64 {
+        let quest_contract: Address = env.storage().instance().get(&DataKey::QuestContract).unwrap();
+        quest_contract.require_auth();
+        
+        Self::mint_badge(
+            &env,
+            owner,
+            AchievementType::OfferLanded,
+            Some(quest_id),
+            Self::get_metadata_uri(&env, &AchievementType::OfferLanded),
+            100,
+        )
+    }
+    
     /// Get badge details
     pub fn get_badge(env: Env, badge_id: u64) -> Option<Badge> {
         env.storage()
@@ -0,0 +1,160 @@
+// contracts/fixed-point/src/lib.rs
+#![no_std]
+
+// Fixed-point scale: 1.0 == 10^18
+pub const SCALE: i128 = 1_000_000_000_000_000_000;
+
+// Basis points per 1.0 (10000 bps == 100%)
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+pub const DAYS_PER_YEAR: i128 = 365;
+
+// Rounding direction for every division
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    Down,    // Toward zero - use for payouts so pools never go short
+    Up,      // Away from zero - use for amounts owed to the protocol
+    Nearest, // Half rounds up
+}
+
+// How interest accrues over the period
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compounding {
+    Simple, // principal * rate * days / 365
+    Daily,  // principal * ((1 + rate / 365) ^ days - 1)
+}
+
+/// Compute `a * b / denominator` with explicit rounding (non-negative inputs)
+pub fn mul_div(a: i128, b: i128, denominator: i128, rounding: Rounding) -> i128 {
+    assert!(a >= 0 && b >= 0, "Negative operand");
+    assert!(denominator > 0, "Invalid denominator");
+    
+    let product = a.checked_mul(b).expect("Fixed-point overflow");
+    let quotient = product / denominator;
+    let remainder = product % denominator;
+    
+    match rounding {
+        Rounding::Down => quotient,
+        Rounding::Up if remainder > 0 => quotient + 1,
+        Rounding::Up => quotient,
+        Rounding::Nearest if remainder * 2 >= denominator => quotient + 1,
+        Rounding::Nearest => quotient,
+    }
+}
+
+/// Multiply two scaled values
+pub fn mul(a: i128, b: i128, rounding: Rounding) -> i128 {
+    mul_div(a, b, SCALE, rounding)
+}
+
+/// Convert an annual rate in basis points to a scaled rate (500 bps -> 0.05 * SCALE)
+pub fn rate_from_bps(bps: u32) -> i128 {
+    mul_div(bps as i128, SCALE, BPS_DENOMINATOR, Rounding::Down)
+}
+
+/// Raise a scaled value to an integer power (exponentiation by squaring)
+pub fn pow(base: i128, exponent: u64, rounding: Rounding) -> i128 {
+    let mut result = SCALE;
+    let mut base = base;
+    let mut exponent = exponent;
+    
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul(result, base, rounding);
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul(base, base, rounding);
+        }
+    }
+    
+    result
+}
+
+/// Interest earned on `principal` at `annual_rate_bps` over `days`
+pub fn accrue_interest(
+    principal: i128,
+    annual_rate_bps: u32,
+    days: u64,
+    compounding: Compounding,
+    rounding: Rounding,
+) -> i128 {
+    let annual_rate = rate_from_bps(annual_rate_bps);
+    
+    match compounding {
+        Compounding::Simple => {
+            let period_rate = mul_div(annual_rate, days as i128, DAYS_PER_YEAR, rounding);
+            mul(principal, period_rate, rounding)
+        }
+        Compounding::Daily => {
+            let daily_rate = mul_div(annual_rate, 1, DAYS_PER_YEAR, rounding);
+            let growth = pow(SCALE + daily_rate, days, rounding);
+            mul(principal, growth - SCALE, rounding)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    
+    #[test]
+    fn mul_div_rounding() {
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down), 3);
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up), 4);
+        assert_eq!(mul_div(10, 1, 3, Rounding::Nearest), 3);
+        assert_eq!(mul_div(11, 1, 2, Rounding::Nearest), 6);
+        assert_eq!(mul_div(12, 1, 3, Rounding::Up), 4);
+    }
+    
+    #[test]
+    fn rate_conversion() {
+        assert_eq!(rate_from_bps(500), SCALE / 20);
+        assert_eq!(rate_from_bps(10_000), SCALE);
+        assert_eq!(rate_from_bps(0), 0);
+    }
+    
+    #[test]
+    fn pow_matches_repeated_multiplication() {
+        let base = SCALE + SCALE / 100;
+        let mut expected = SCALE;
+        for _ in 0..10 {
+            expected = mul(expected, base, Rounding::Down);
+        }
+        assert_eq!(pow(base, 10, Rounding::Down), expected);
+        assert_eq!(pow(base, 0, Rounding::Down), SCALE);
+    }
+    
+    // Reference values computed at 60-digit precision
+    #[test]
+    fn simple_interest_reference_values() {
+        // 100 USDC, 5%, 90 days = 1.2328767123...
+        assert_eq!(accrue_interest(100_000_000, 500, 90, Compounding::Simple, Rounding::Down), 1_232_876);
+        assert_eq!(accrue_interest(100_000_000, 500, 90, Compounding::Simple, Rounding::Up), 1_232_877);
+        // 10 USDC, 5%, 7 days = 0.0095890410...
+        assert_eq!(accrue_interest(10_000_000, 500, 7, Compounding::Simple, Rounding::Down), 9_589);
+        // 50 USDC, 5%, 30 days = 0.2054794520...
+        assert_eq!(accrue_interest(50_000_000, 500, 30, Compounding::Simple, Rounding::Nearest), 205_479);
+        // Full year is exactly the annual rate
+        assert_eq!(accrue_interest(1_000_000_000, 500, 365, Compounding::Simple, Rounding::Down), 50_000_000);
+    }
+    
+    #[test]
+    fn daily_compounding_reference_values() {
+        // 100 USDC, 5%, 90 days = 1.2404224830...
+        assert_eq!(accrue_interest(100_000_000, 500, 90, Compounding::Daily, Rounding::Down), 1_240_422);
+        // 10 USDC, 5%, 7 days = 0.0095929826...
+        assert_eq!(accrue_interest(10_000_000, 500, 7, Compounding::Daily, Rounding::Down), 9_592);
+        // 50 USDC, 5%, 30 days = 0.2058881184...
+        assert_eq!(accrue_interest(50_000_000, 500, 30, Compounding::Daily, Rounding::Down), 205_888);
+        // 100 USDC, 5%, 365 days = 5.1267496467...
+        assert_eq!(accrue_interest(1_000_000_000, 500, 365, Compounding::Daily, Rounding::Down), 51_267_496);
+    }
+    
+    #[test]
+    fn zero_inputs_accrue_nothing() {
+        assert_eq!(accrue_interest(0, 500, 90, Compounding::Daily, Rounding::Up), 0);
+        assert_eq!(accrue_interest(100_000_000, 0, 90, Compounding::Daily, Rounding::Up), 0);
+        assert_eq!(accrue_interest(100_000_000, 500, 0, Compounding::Simple, Rounding::Up), 0);
+    }
+}
@@ -1,8 +1,6 @@
 #![no_std]
 use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map};
 
-const USERS_KEY: &str = "USERS";
-
 #[contracttype]
 #[derive(Clone)]
 pub struct Player {
@@ -1,5 +1,6 @@
 // contracts/verification/src/lib.rs
 #![no_std]
+use admin_auth::AdminSigners;
 use soroban_sdk::{
     contract, contractimpl, contracttype, Address, Bytes, Env, String, Vec
 };
@@ -11,6 +12,7 @@ pub enum VerificationMethod {
     ZKEmail,          // ZK Email proof for job application confirmations
     LinkedInOracle,   // Oracle verification for LinkedIn posts
     Manual,           // Manual submission with proof
+    JobOffer,         // Offer letter confirmed by an oracle
 }
 
 // Verification status
@@ -51,7 +53,6 @@ pub struct Oracle {
 
 #[contracttype]
 pub enum DataKey {
-    Admin,
     QuestContract,
     ProofCounter,
     Proof(u64),
@@ -77,21 +78,56 @@ impl VerificationContract {
     ) {
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Admin, &admin);
+        admin_auth::set_admin(&env, &admin);
         env.storage().instance().set(&DataKey::QuestContract, &quest_contract);
         env.storage().instance().set(&DataKey::ZKVerifier, &zk_verifier);
         env.storage().instance().set(&DataKey::ProofCounter, &0u64);
         env.storage().instance().set(&DataKey::OracleList, &Vec::<Address>::new(&env));
     }
     
+    /// Propose a new admin (takes effect once the new admin accepts)
+    pub fn propose_admin(env: Env, new_admin: Address, approvers: Vec<Address>) {
+        admin_auth::propose_admin(&env, &new_admin, &approvers);
+    }
+    
+    /// Accept a pending admin handoff
+    pub fn accept_admin(env: Env) {
+        admin_auth::accept_admin(&env);
+    }
+    
+    /// Set the M-of-N signer set that replaces admin auth for admin operations (threshold 0 disables it)
+    pub fn set_admin_signers(
+        env: Env,
+        signers: Vec<Address>,
+        threshold: u32,
+        approvers: Vec<Address>,
+    ) {
+        admin_auth::set_admin_signers(&env, signers, threshold, &approvers);
+    }
+    
+    /// Get current admin
+    pub fn get_admin(env: Env) -> Address {
+        admin_auth::get_admin(&env)
+    }
+    
+    /// Get pending admin, if a handoff is in progress
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        admin_auth::get_pending_admin(&env)
+    }
+    
+    /// Get admin signer set, if multisig is enabled
+    pub fn get_admin_signers(env: Env) -> Option<AdminSigners> {
+        admin_auth::get_admin_signers(&env)
+    }
+    
     /// Register an oracle
     pub fn register_oracle(
         env: Env,
         oracle_address: Address,
         endpoint: String,
+        approvers: Vec<Address>,
     ) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+        admin_auth::require_admin(&env, &approvers);
         
         let oracle = Oracle {
             address: oracle_address.clone(),
@@ -126,7 +162,7 @@ impl VerificationContract {
         // let is_valid = Self::verify_zk_proof(&env, &zk_verifier, &proof_hash);
         // assert!(is_valid, "Invalid ZK proof");
         
-        let proof_id = Self::create_proof(
+        Self::create_proof(
             &env,
             quest_id,
             user,
@@ -135,9 +171,7 @@ impl VerificationContract {
             verification_data,
             VerificationStatus::Verified,  // Auto-verify ZK proofs
             None,
-        );
-        
-        proof_id
+        )
     }
     
     /// Request LinkedIn verification via oracle
@@ -151,7 +185,7 @@ impl VerificationContract {
         
         // Create pending verification
         let proof_hash = Bytes::new(&env);  // Will be filled by oracle
-        let proof_id = Self::create_proof(
+        Self::create_proof(
             &env,
             quest_id,
             user,
@@ -160,9 +194,7 @@ impl VerificationContract {
             linkedin_post_url,
             VerificationStatus::Pending,
             None,
-        );
-        
-        proof_id
+        )
     }
     
     /// Oracle verifies LinkedIn post
@@ -225,7 +257,7 @@ impl VerificationContract {
     ) -> u64 {
         user.require_auth();
         
-        let proof_id = Self::create_proof(
+        Self::create_proof(
             &env,
             quest_id,
             user,
@@ -234,15 +266,12 @@ impl VerificationContract {
             verification_data,
             VerificationStatus::Pending,
             None,
-        );
-        
-        proof_id
+        )
     }
     
     /// Admin approves manual proof
-    pub fn approve_manual_proof(env: Env, proof_id: u64, approve: bool) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+    pub fn approve_manual_proof(env: Env, proof_id: u64, approve: bool, approvers: Vec<Address>) {
+        let admin = admin_auth::require_admin(&env, &approvers);
         
         let mut proof: VerificationProof = env.storage()
             .persistent()
@@ -266,6 +295,77 @@ impl VerificationContract {
         }
     }
     
+    /// Submit a job offer for oracle confirmation
+    pub fn submit_job_offer(
+        env: Env,
+        quest_id: u64,
+        user: Address,
+        proof_hash: Bytes,
+        verification_data: String,
+    ) -> u64 {
+        user.require_auth();
+        
+        Self::create_proof(
+            &env,
+            quest_id,
+            user,
+            VerificationMethod::JobOffer,
+            proof_hash,
+            verification_data,
+            VerificationStatus::Pending,
+            None,
+        )
+    }
+    
+    /// Oracle confirms or rejects a job offer
+    pub fn verify_job_offer(env: Env, proof_id: u64, oracle: Address, is_valid: bool) {
+        oracle.require_auth();
+        
+        let mut oracle_info: Oracle = env.storage()
+            .persistent()
+            .get(&DataKey::Oracle(oracle.clone()))
+            .expect("Oracle not registered");
+        
+        assert!(oracle_info.is_active, "Oracle is not active");
+        
+        let mut proof: VerificationProof = env.storage()
+            .persistent()
+            .get(&DataKey::Proof(proof_id))
+            .unwrap_or_else(|| panic!("Proof {} not found", proof_id));
+        
+        assert!(proof.status == VerificationStatus::Pending, "Proof already processed");
+        assert!(proof.method == VerificationMethod::JobOffer, "Wrong verification method");
+        
+        proof.status = if is_valid {
+            VerificationStatus::Verified
+        } else {
+            VerificationStatus::Rejected
+        };
+        proof.verifier = Some(oracle.clone());
+        
+        env.storage().persistent().set(&DataKey::Proof(proof_id), &proof);
+        
+        oracle_info.verifications_count += 1;
+        if is_valid {
+            oracle_info.reputation += 1;
+        }
+        env.storage().persistent().set(&DataKey::Oracle(oracle), &oracle_info);
+    }
+    
+    /// Check whether a proof is a confirmed job offer for the given quest and user
+    pub fn is_verified_offer(env: Env, proof_id: u64, quest_id: u64, user: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get::<DataKey, VerificationProof>(&DataKey::Proof(proof_id))
+            .map(|proof| {
+                proof.method == VerificationMethod::JobOffer
+                    && proof.status == VerificationStatus::Verified
+                    && proof.quest_id == quest_id
+                    && proof.user == user
+            })
+            .unwrap_or(false)
+    }
+    
     /// Get proof details
     pub fn get_proof(env: Env, proof_id: u64) -> Option<VerificationProof> {
         env.storage()
@@ -297,7 +397,17 @@ impl VerificationContract {
             .expect("Oracle not found")
     }
     
+    /// Check if an address is a registered, active oracle
+    pub fn is_active_oracle(env: Env, oracle: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get::<DataKey, Oracle>(&DataKey::Oracle(oracle))
+            .map(|info| info.is_active)
+            .unwrap_or(false)
+    }
+    
     // Helper: Create proof
+    #[allow(clippy::too_many_arguments)]
     fn create_proof(
         env: &Env,
         quest_id: u64,
@@ -1,7 +1,9 @@
 // contracts/yield/src/lib.rs
 #![no_std]
+use admin_auth::AdminSigners;
+use fixed_point::{accrue_interest, Compounding, Rounding};
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Env, Vec
+    contract, contractimpl, contracttype, Address, Env, Vec
 };
 
 // Yield strategies available on Stellar
@@ -40,7 +42,6 @@ pub struct YieldPoolStats {
 
 #[contracttype]
 pub enum DataKey {
-    Admin,
     QuestContract,
     TokenAddress,
     PositionCounter,
@@ -52,6 +53,9 @@ pub enum DataKey {
     LendingPoolAddress,
 }
 
+// Simulated protocol return until real DeFi integrations land (5% APY)
+const SIMULATED_APY_BPS: u32 = 500;
+
 #[contract]
 pub struct YieldContract;
 
@@ -67,7 +71,7 @@ impl YieldContract {
     ) {
         admin.require_auth();
         
-        env.storage().instance().set(&DataKey::Admin, &admin);
+        admin_auth::set_admin(&env, &admin);
         env.storage().instance().set(&DataKey::QuestContract, &quest_contract);
         env.storage().instance().set(&DataKey::TokenAddress, &token);
         env.storage().instance().set(&DataKey::PositionCounter, &0u64);
@@ -83,25 +87,59 @@ impl YieldContract {
         env.storage().instance().set(&DataKey::PoolStats, &initial_stats);
     }
     
+    /// Propose a new admin (takes effect once the new admin accepts)
+    pub fn propose_admin(env: Env, new_admin: Address, approvers: Vec<Address>) {
+        admin_auth::propose_admin(&env, &new_admin, &approvers);
+    }
+    
+    /// Accept a pending admin handoff
+    pub fn accept_admin(env: Env) {
+        admin_auth::accept_admin(&env);
+    }
+    
+    /// Set the M-of-N signer set that replaces admin auth for admin operations (threshold 0 disables it)
+    pub fn set_admin_signers(
+        env: Env,
+        signers: Vec<Address>,
+        threshold: u32,
+        approvers: Vec<Address>,
+    ) {
+        admin_auth::set_admin_signers(&env, signers, threshold, &approvers);
+    }
+    
+    /// Get current admin
+    pub fn get_admin(env: Env) -> Address {
+        admin_auth::get_admin(&env)
+    }
+    
+    /// Get pending admin, if a handoff is in progress
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        admin_auth::get_pending_admin(&env)
+    }
+    
+    /// Get admin signer set, if multisig is enabled
+    pub fn get_admin_signers(env: Env) -> Option<AdminSigners> {
+        admin_auth::get_admin_signers(&env)
+    }
+    
     /// Configure DeFi protocol addresses
     pub fn configure_protocols(
         env: Env,
         liquidity_pool: Address,
         lending_pool: Address,
+        approvers: Vec<Address>,
     ) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+        admin_auth::require_admin(&env, &approvers);
         
         env.storage().instance().set(&DataKey::LiquidityPoolAddress, &liquidity_pool);
         env.storage().instance().set(&DataKey::LendingPoolAddress, &lending_pool);
     }
     
     /// Deposit funds from quest contract
-    pub fn deposit(env: Env, amount: i128) -> u64 {
+    pub fn deposit(env: Env, amount: i128, approvers: Vec<Address>) -> u64 {
         // For testing: allow admin to call this
         // In production: only quest contract should call this
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+        admin_auth::require_admin(&env, &approvers);
         
         // For testing: skip actual token transfer
         // In production, uncomment this:
@@ -126,11 +164,10 @@ impl YieldContract {
     }
     
     /// Withdraw funds and return to quest contract
-    pub fn withdraw(env: Env, position_id: u64) -> (i128, i128) {
+    pub fn withdraw(env: Env, position_id: u64, approvers: Vec<Address>) -> (i128, i128) {
         // For testing: allow admin to call this
         // In production: only quest contract should call this
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+        admin_auth::require_admin(&env, &approvers);
         
         let position: Position = env.storage()
             .persistent()
@@ -186,11 +223,7 @@ impl YieldContract {
             .persistent()
             .get(&DataKey::Position(position_id));
         
-        if let Some(pos) = position {
-            Some(Self::update_position(env, position_id))
-        } else {
-            None
-        }
+        position.map(|_| Self::update_position(env, position_id))
     }
     
     /// Get pool statistics
@@ -201,15 +234,14 @@ impl YieldContract {
     /// Calculate estimated yield for amount and duration
     pub fn estimate_yield(env: Env, amount: i128, days: u32) -> i128 {
         let stats: YieldPoolStats = env.storage().instance().get(&DataKey::PoolStats).unwrap();
-        let apy = stats.current_apy as i128;
         
-        // Calculate daily yield
-        let daily_rate = apy * 100 / 36500; // Basis points per day
-        (amount * daily_rate * days as i128) / 10000
+        // Daily-compounded yield at the current APY, rounded down (the quest contract pays
+        // simple interest on stake-days, so its payouts come in slightly under this estimate)
+        accrue_interest(amount, stats.current_apy, days as u64, Compounding::Daily, Rounding::Down)
     }
     
     // Helper: Determine optimal yield strategy
-    fn determine_optimal_strategy(env: &Env) -> YieldStrategy {
+    fn determine_optimal_strategy(_env: &Env) -> YieldStrategy {
         // In production, this would analyze:
         // - Current APYs across protocols
         // - Liquidity availability
@@ -265,7 +297,7 @@ impl YieldContract {
     }
     
     // Helper: Invest in liquidity pool
-    fn invest_liquidity_pool(env: &Env, amount: i128) {
+    fn invest_liquidity_pool(env: &Env, _amount: i128) {
         // In production:
         // 1. Get liquidity pool contract
         // 2. Approve token spend
@@ -277,7 +309,7 @@ impl YieldContract {
     }
     
     // Helper: Invest in lending protocol
-    fn invest_lending(env: &Env, amount: i128) {
+    fn invest_lending(env: &Env, _amount: i128) {
         // In production:
         // 1. Get lending pool contract (e.g., Blend)
         // 2. Approve token spend
@@ -360,8 +392,13 @@ impl YieldContract {
         // In production, query the actual protocol for current value
         // For now, simulate 5% APY
         let days_elapsed = (env.ledger().timestamp() - position.created_at) / 86400;
-        let daily_rate = 500 * 100 / 36500; // 5% APY to daily rate in basis points
-        let yield_earned = (position.amount_invested * daily_rate * days_elapsed as i128) / 10000;
+        let yield_earned = accrue_interest(
+            position.amount_invested,
+            SIMULATED_APY_BPS,
+            days_elapsed,
+            Compounding::Daily,
+            Rounding::Down,
+        );
         
         position.amount_invested + yield_earned
     }
@@ -383,4 +420,6 @@ impl YieldContract {
         
         env.storage().instance().set(&DataKey::ActivePositions, &new_active);
     }
-}
\ No newline at end of file
+}
+
+mod test;
\ No newline at end of file
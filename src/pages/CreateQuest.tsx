@@ -3,6 +3,7 @@ import { useState } from "react";
 import { useNavigate } from "react-router-dom";
 import { useWallet } from "../hooks/useWallet";
 import { Client } from "../contracts/dist";
+import { CONTRACTS } from "../config/contracts";
 
 const CreateQuest = () => {
   const navigate = useNavigate();
@@ -32,10 +33,13 @@ const CreateQuest = () => {
       
       const tx = await client.create_quest({
         user: address,
+        asset: CONTRACTS.USDC,
         quest_type: questTypeEnum,
+        activity_weights: new Map(), // default: one point per activity of the quest type
         daily_target: dailyTarget,
         duration_days: duration,
         grace_days: 1,
+        schedule: 0b1111111, // every weekday required
       });
       
       const sent = await tx.signAndSend();
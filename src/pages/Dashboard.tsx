@@ -73,10 +73,13 @@ const Dashboard: React.FC = () => {
         signTransaction,
       });
       
+      const quest = quests.find(q => q.id === questId);
+      if (!quest) throw new Error(`Quest #${questId} is no longer loaded`);
+      const [kind] = quest.activity_weights.keys();
       const proofHash = "proof_" + Date.now();
       const tx = await client.log_activity({
         quest_id: questId,
-        activities_count: 5,
+        activities: new Map([[kind, 5]]),
         verification_hash: proofHash
       });
       